@@ -0,0 +1,43 @@
+use std::env;
+use std::error::Error;
+
+use crate::Settings;
+
+const USERNAME_ENV_VAR: &str = "SPACETRACK_USERNAME";
+const PASSWORD_ENV_VAR: &str = "SPACETRACK_PASSWORD";
+
+/// Resolves the space-track username, preferring the `--username` CLI override,
+/// then the config file, then the `SPACETRACK_USERNAME` environment variable.
+pub fn resolve_username(settings: &Settings, cli_value: Option<&str>) -> Result<String, Box<dyn Error>> {
+	if let Some(v) = cli_value {
+		return Ok(v.to_string());
+	}
+	if let Some(v) = &settings.username {
+		return Ok(v.clone());
+	}
+	if let Ok(v) = env::var(USERNAME_ENV_VAR) {
+		return Ok(v);
+	}
+	Err(format!(
+		"no space-track username configured: set `username` in the config file, pass --username, or set {}",
+		USERNAME_ENV_VAR
+	).into())
+}
+
+/// Resolves the space-track password, preferring the `--password-stdin` CLI override,
+/// then the config file, then the `SPACETRACK_PASSWORD` environment variable.
+pub fn resolve_password(settings: &Settings, cli_value: Option<String>) -> Result<String, Box<dyn Error>> {
+	if let Some(v) = cli_value {
+		return Ok(v);
+	}
+	if let Some(v) = &settings.password {
+		return Ok(v.clone());
+	}
+	if let Ok(v) = env::var(PASSWORD_ENV_VAR) {
+		return Ok(v);
+	}
+	Err(format!(
+		"no space-track password configured: set `password` in the config file, pass --password-stdin, or set {}",
+		PASSWORD_ENV_VAR
+	).into())
+}
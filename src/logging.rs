@@ -0,0 +1,107 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use log::LevelFilter;
+use log4rs::append::console::ConsoleAppender;
+use log4rs::append::file::FileAppender;
+use log4rs::append::rolling_file::policy::compound::roll::fixed_window::FixedWindowRoller;
+use log4rs::append::rolling_file::policy::compound::trigger::size::SizeTrigger;
+use log4rs::append::rolling_file::policy::compound::CompoundPolicy;
+use log4rs::append::rolling_file::RollingFileAppender;
+use log4rs::append::Append;
+use log4rs::config::{Appender, Config, Root};
+use log4rs::encode::json::JsonEncoder;
+use log4rs::encode::pattern::PatternEncoder;
+use log4rs::encode::Encode;
+use serde::Deserialize;
+
+const DEFAULT_PATTERN: &str = "{d(%Y-%m-%d %T%.3f)(utc)} [{l}] - {m}{n}";
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogBackend {
+	Stdout,
+	File,
+	RollingFile,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+	Pattern,
+	Json,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LoggingSettings {
+	#[serde(default = "default_backend")]
+	pub backend: LogBackend,
+	#[serde(default = "default_format")]
+	pub format: LogFormat,
+	#[serde(default)]
+	pub directory: Option<String>,
+	#[serde(default)]
+	pub filename_prefix: Option<String>,
+	#[serde(default = "default_max_size_bytes")]
+	pub max_size_bytes: u64,
+	#[serde(default = "default_retained_files")]
+	pub retained_files: u32,
+}
+
+impl Default for LoggingSettings {
+	fn default() -> Self {
+		LoggingSettings {
+			backend: default_backend(),
+			format: default_format(),
+			directory: None,
+			filename_prefix: None,
+			max_size_bytes: default_max_size_bytes(),
+			retained_files: default_retained_files(),
+		}
+	}
+}
+
+fn default_backend() -> LogBackend { LogBackend::Stdout }
+fn default_format() -> LogFormat { LogFormat::Pattern }
+fn default_max_size_bytes() -> u64 { 10 * 1024 * 1024 }
+fn default_retained_files() -> u32 { 5 }
+
+fn encoder(format: LogFormat) -> Box<dyn Encode> {
+	match format {
+		LogFormat::Pattern => Box::new(PatternEncoder::new(DEFAULT_PATTERN)),
+		LogFormat::Json => Box::new(JsonEncoder::new()),
+	}
+}
+
+fn log_path(settings: &LoggingSettings, suffix: &str) -> PathBuf {
+	let dir = settings.directory.as_deref().unwrap_or(".");
+	let prefix = settings.filename_prefix.as_deref().unwrap_or("tle_retriever");
+	let mut path = PathBuf::from(dir);
+	path.push(format!("{}{}", prefix, suffix));
+	path
+}
+
+/// Builds the log4rs `Config` for the configured backend/format, replacing the
+/// bootstrap console config once settings have been parsed.
+pub fn build_log_config(settings: &LoggingSettings, level: LevelFilter) -> Result<Config, Box<dyn Error>> {
+	let appender: Box<dyn Append> = match settings.backend {
+		LogBackend::Stdout => Box::new(ConsoleAppender::builder().encoder(encoder(settings.format)).build()),
+		LogBackend::File => {
+			let path = log_path(settings, ".log");
+			Box::new(FileAppender::builder().encoder(encoder(settings.format)).build(path)?)
+		}
+		LogBackend::RollingFile => {
+			let base_path = log_path(settings, ".log");
+			let roll_pattern = log_path(settings, ".{}.log.gz");
+			let trigger = SizeTrigger::new(settings.max_size_bytes);
+			let roller = FixedWindowRoller::builder().build(&roll_pattern.to_string_lossy(), settings.retained_files)?;
+			let policy = CompoundPolicy::new(Box::new(trigger), Box::new(roller));
+			Box::new(RollingFileAppender::builder()
+				.encoder(encoder(settings.format))
+				.build(base_path, Box::new(policy))?)
+		}
+	};
+	Ok(Config::builder()
+		.appender(Appender::builder().build("app", appender))
+		.build(Root::builder().appender("app").build(level))?)
+}
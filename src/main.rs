@@ -1,15 +1,24 @@
 use std::error::Error;
 use std::path::PathBuf;
 use std::fs::File;
-use std::io::{LineWriter,Write};
+use std::io::LineWriter;
+use std::time::Duration;
 use serde::{Serialize,Deserialize};
 use config::Config as CConfig;
-use log::{info,debug,LevelFilter};
+use log::{info,debug,warn,LevelFilter};
 use log4rs::append::console::ConsoleAppender;
-use log4rs::encode::pattern::PatternEncoder;
 use log4rs::config::{Appender, Root};
 use log4rs::Config;
 use clap::{Arg, ArgAction, Command};
+use rand::Rng;
+
+mod credentials;
+mod logging;
+mod output;
+mod tle;
+
+use logging::LoggingSettings;
+use output::OutputFormat;
 
 #[derive(Serialize, Deserialize,Debug)]
 pub struct STResponse {
@@ -31,14 +40,24 @@ pub struct STResponse {
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Settings {
-	pub username: String,
-	pub password: String,
+	#[serde(default)]
+	pub username: Option<String>,
+	#[serde(default)]
+	pub password: Option<String>,
 	pub norad_ids: Vec<u32>,
 	pub connection_timeout: u32,
 	pub connection_read_timeout: u32,
 	pub connection_retries: u8,
 	pub output_filename: String,
 	pub output_directory: String,
+	#[serde(default = "default_output_format")]
+	pub output_format: OutputFormat,
+	#[serde(default)]
+	pub logging: LoggingSettings,
+}
+
+fn default_output_format() -> OutputFormat {
+	OutputFormat::ThreeLe
 }
 
 impl Settings {
@@ -50,6 +69,80 @@ impl Settings {
 	}
 }
 
+/// Builds a `ureq::Agent` whose connect/read timeouts match the configured settings.
+fn build_agent(settings: &Settings) -> ureq::Agent {
+	ureq::AgentBuilder::new()
+		.timeout_connect(Duration::from_secs(settings.connection_timeout as u64))
+		.timeout_read(Duration::from_secs(settings.connection_read_timeout as u64))
+		.build()
+}
+
+/// Transient failures are worth retrying: connection/timeout errors, 5xx, and space-track's 429.
+/// Everything else (4xx auth failures, bad requests) is treated as terminal.
+fn is_transient_error(err: &ureq::Error) -> bool {
+	match err {
+		ureq::Error::Status(code, _) => *code == 429 || *code >= 500,
+		ureq::Error::Transport(_) => true,
+	}
+}
+
+/// Runs `send` up to `retries` additional times on transient failure, sleeping with
+/// exponential backoff (plus a little jitter) between attempts.
+fn send_with_retry<F>(description: &str, retries: u8, mut send: F) -> Result<ureq::Response, Box<dyn Error>>
+where
+	F: FnMut() -> Result<ureq::Response, ureq::Error>,
+{
+	let base_delay_ms: u64 = 250;
+	let mut attempt: u32 = 0;
+	loop {
+		match send() {
+			Ok(response) => return Ok(response),
+			Err(err) if attempt < retries as u32 && is_transient_error(&err) => {
+				let backoff_ms = base_delay_ms.saturating_mul(2u64.saturating_pow(attempt));
+				let jitter_ms: u64 = rand::thread_rng().gen_range(0..=100);
+				let delay = Duration::from_millis(backoff_ms.saturating_add(jitter_ms));
+				warn!("{} failed on attempt {}: {}; retrying in {:?}", description, attempt + 1, err, delay);
+				std::thread::sleep(delay);
+				attempt += 1;
+			}
+			Err(err) => return Err(Box::new(err)),
+		}
+	}
+}
+
+/// Logs in and runs `query` against space-track in a single call (space-track accepts
+/// the query alongside the login form), retrying transient failures.
+fn login_and_query(agent: &ureq::Agent, username: &str, password: &str, query: &str, retries: u8) -> Result<Vec<STResponse>, Box<dyn Error>> {
+	let response = send_with_retry("space-track login+query", retries, || {
+		agent.post("https://www.space-track.org/ajaxauth/login").send_form(&[
+			("identity", username),
+			("password", password),
+			("query", query),
+		])
+	})?;
+	Ok(response.into_json()?)
+}
+
+/// Performs a lightweight health probe against space-track: log in, then issue a
+/// single-row query, without creating or touching the output file.
+fn run_check(settings: &Settings, username: &str, password: &str) -> Result<(), Box<dyn Error>> {
+	let agent = build_agent(settings);
+	let probe_id = settings.norad_ids.first().copied().unwrap_or(25544);
+	let mut query = "https://www.space-track.org/basicspacedata/query/class/gp/NORAD_CAT_ID/".to_string();
+	query.push_str(&probe_id.to_string());
+	query.push_str("/limit/1/format/json");
+	match login_and_query(&agent, username, password, &query, settings.connection_retries) {
+		Ok(sts) => {
+			println!("OK: authenticated and queried NORAD ID {} ({} record(s))", probe_id, sts.len());
+			Ok(())
+		}
+		Err(e) => {
+			println!("FAIL: {}", e);
+			std::process::exit(1);
+		}
+	}
+}
+
 fn main() -> Result<(),Box<dyn Error>> {
 	let stdout = ConsoleAppender::builder().build();
 	info!("Starting up");
@@ -68,8 +161,8 @@ fn main() -> Result<(),Box<dyn Error>> {
 						.short('c')
 						.long("config")
 						.action(ArgAction::Set)
-						.required(true)
 						.num_args(1)
+						.global(true)
 					)
 				.arg(
 					Arg::new("loglevel")
@@ -77,31 +170,77 @@ fn main() -> Result<(),Box<dyn Error>> {
 						.long("loglevel")
 						.help("logging level off, error, info, debug, trace")
 						.action(ArgAction::Set)
+						.global(true)
+				)
+				.arg(
+					Arg::new("strict")
+						.long("strict")
+						.help("abort on the first invalid TLE instead of logging a warning and skipping it")
+						.action(ArgAction::SetTrue)
+				)
+				.arg(
+					Arg::new("format")
+						.long("format")
+						.help("output format: 3le, json or csv (overrides output_format in the config file)")
+						.action(ArgAction::Set)
+				)
+				.arg(
+					Arg::new("username")
+						.long("username")
+						.help("space-track username (overrides the config file and SPACETRACK_USERNAME)")
+						.action(ArgAction::Set)
+						.global(true)
+				)
+				.arg(
+					Arg::new("password-stdin")
+						.long("password-stdin")
+						.help("read the space-track password from stdin instead of the config file or SPACETRACK_PASSWORD")
+						.action(ArgAction::SetTrue)
+						.global(true)
+				)
+				.subcommand(
+					Command::new("check")
+						.about("Verify space-track connectivity and credentials without writing output")
 				)
 	.get_matches();
-	if let Some(loglevel) = matches.get_one::<String>("loglevel") {
-		let lfilter = match loglevel.as_str() {
-			"off"   => LevelFilter::Off,
-			"error" => LevelFilter::Error,
-			"warn"  => LevelFilter::Warn,
-			"info"  => LevelFilter::Info,
-			"debug" => LevelFilter::Debug,
-			"trace" => LevelFilter::Trace,
-			&_      => return Err("Invalid loglevel, needs to be one of: off,error,warn,info,debug or trace".into()),
-		};
-		let stdout = ConsoleAppender::builder()
-			.encoder(Box::new(PatternEncoder::new("{d(%Y-%m-%d %T%.3f)(utc)} [{l}] - {m}{n}")))
-			.build();
-		let config = Config::builder()
-			.appender(Appender::builder().build("stdout", Box::new(stdout)))
-			.build(Root::builder().appender("stdout").build(lfilter))
-			.unwrap();
-		loghandle.set_config(config);
+	let level = match matches.get_one::<String>("loglevel").map(|s| s.as_str()) {
+		None          => LevelFilter::Info,
+		Some("off")   => LevelFilter::Off,
+		Some("error") => LevelFilter::Error,
+		Some("warn")  => LevelFilter::Warn,
+		Some("info")  => LevelFilter::Info,
+		Some("debug") => LevelFilter::Debug,
+		Some("trace") => LevelFilter::Trace,
+		Some(_)       => return Err("Invalid loglevel, needs to be one of: off,error,warn,info,debug or trace".into()),
+	};
+	let config_file = match matches.get_one::<String>("config") {
+		Some(c) => c,
+		None => return Err("the --config <FILE> argument is required".into()),
+	};
+	let strict = matches.get_flag("strict");
+	let mut settings = Settings::new(config_file)?;
+	if let Some(format) = matches.get_one::<String>("format") {
+		settings.output_format = OutputFormat::parse(format)?;
 	}
-	let config_file = matches.get_one::<String>("config").unwrap();
-	let settings = Settings::new(config_file)?;
 	debug!("{:#?}",settings);
 
+	let cli_password = if matches.get_flag("password-stdin") {
+		let mut line = String::new();
+		std::io::stdin().read_line(&mut line)?;
+		Some(line.trim_end_matches(['\r', '\n']).to_string())
+	} else {
+		None
+	};
+	let username = credentials::resolve_username(&settings, matches.get_one::<String>("username").map(|s| s.as_str()))?;
+	let password = credentials::resolve_password(&settings, cli_password)?;
+
+	let log_config = logging::build_log_config(&settings.logging, level)?;
+	loghandle.set_config(log_config);
+
+	if matches.subcommand_matches("check").is_some() {
+		return run_check(&settings, &username, &password);
+	}
+
 	// construct output_filename
 	let mut filename = PathBuf::from(settings.output_directory);
 	filename.push(settings.output_filename);
@@ -110,25 +249,22 @@ fn main() -> Result<(),Box<dyn Error>> {
     let mut file = LineWriter::new(file);
 
 	let mut query = "https://www.space-track.org/basicspacedata/query/class/gp/NORAD_CAT_ID/".to_string();
-	let nids: String = settings.norad_ids.into_iter().map(|n| n.to_string()).collect::<Vec<_>>().join(",");
+	let nids: String = settings.norad_ids.clone().into_iter().map(|n| n.to_string()).collect::<Vec<_>>().join(",");
 	query.push_str(&nids);
 	query.push_str("/orderby/TLE_LINE1%20ASC/format/json");
-	let response = ureq::post("https://www.space-track.org/ajaxauth/login").send_form(&[
-		("identity", &settings.username),
-		("password", &settings.password),
-		("query", &query),
-	])?;
-	let sts:Vec<STResponse> = response.into_json()?;
+	let agent = build_agent(&settings);
+	let sts = login_and_query(&agent, &username, &password, &query, settings.connection_retries)?;
+	let mut validated = Vec::with_capacity(sts.len());
 	for resp in sts {
-		let name = resp.object_name.unwrap_or("Unknown".to_string());
-		let line1 = resp.line_1;
-		let line2 = resp.line_2;
-		file.write_all(name.as_bytes())?;
-		file.write_all(b"\n")?;
-		file.write_all(line1.as_bytes())?;
-		file.write_all(b"\n")?;
-		file.write_all(line2.as_bytes())?;
-		file.write_all(b"\n")?;
+		if let Err(e) = tle::validate_response(&resp) {
+			if strict {
+				return Err(Box::new(e));
+			}
+			warn!("Skipping NORAD {} with invalid TLE: {}", resp.norad_id, e);
+			continue;
+		}
+		validated.push(resp);
 	}
+	output::write_output(&mut file, &validated, settings.output_format)?;
 	Ok(())
 }
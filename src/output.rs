@@ -0,0 +1,101 @@
+use std::error::Error;
+use std::io::Write;
+
+use serde::Deserialize;
+
+use crate::STResponse;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+	#[serde(rename = "3le")]
+	ThreeLe,
+	Json,
+	Csv,
+}
+
+impl OutputFormat {
+	/// Parses a `--format` CLI value; accepts the same spellings as the config file.
+	pub fn parse(s: &str) -> Result<Self, String> {
+		match s.to_lowercase().as_str() {
+			"3le" => Ok(OutputFormat::ThreeLe),
+			"json" => Ok(OutputFormat::Json),
+			"csv" => Ok(OutputFormat::Csv),
+			other => Err(format!("Invalid output format '{}', expected one of: 3le, json, csv", other)),
+		}
+	}
+}
+
+/// Writes the retrieved element sets to `writer` in the requested format.
+pub fn write_output<W: Write>(writer: &mut W, responses: &[STResponse], format: OutputFormat) -> Result<(), Box<dyn Error>> {
+	match format {
+		OutputFormat::ThreeLe => write_3le(writer, responses),
+		OutputFormat::Json => write_json(writer, responses),
+		OutputFormat::Csv => write_csv(writer, responses),
+	}
+}
+
+fn write_3le<W: Write>(writer: &mut W, responses: &[STResponse]) -> Result<(), Box<dyn Error>> {
+	for resp in responses {
+		let name = resp.object_name.as_deref().unwrap_or("Unknown");
+		writer.write_all(name.as_bytes())?;
+		writer.write_all(b"\n")?;
+		writer.write_all(resp.line_1.as_bytes())?;
+		writer.write_all(b"\n")?;
+		writer.write_all(resp.line_2.as_bytes())?;
+		writer.write_all(b"\n")?;
+	}
+	Ok(())
+}
+
+fn write_json<W: Write>(writer: &mut W, responses: &[STResponse]) -> Result<(), Box<dyn Error>> {
+	serde_json::to_writer_pretty(writer, responses)?;
+	Ok(())
+}
+
+fn write_csv<W: Write>(writer: &mut W, responses: &[STResponse]) -> Result<(), Box<dyn Error>> {
+	writer.write_all(b"norad_id,object_name,epoch,line_1,line_2\n")?;
+	for resp in responses {
+		let name = resp.object_name.as_deref().unwrap_or("");
+		writer.write_all(format!(
+			"{},{},{},{},{}\n",
+			resp.norad_id, csv_escape(name), resp.datetime, resp.line_1, resp.line_2,
+		).as_bytes())?;
+	}
+	Ok(())
+}
+
+fn csv_escape(field: &str) -> String {
+	if field.contains(',') || field.contains('"') || field.contains('\n') {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		field.to_string()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_accepts_known_formats_case_insensitively() {
+		assert_eq!(OutputFormat::parse("3le").unwrap(), OutputFormat::ThreeLe);
+		assert_eq!(OutputFormat::parse("JSON").unwrap(), OutputFormat::Json);
+		assert_eq!(OutputFormat::parse("Csv").unwrap(), OutputFormat::Csv);
+	}
+
+	#[test]
+	fn parse_rejects_unknown_format() {
+		assert!(OutputFormat::parse("xml").is_err());
+	}
+
+	#[test]
+	fn csv_escape_passes_through_plain_fields() {
+		assert_eq!(csv_escape("ISS (ZARYA)"), "ISS (ZARYA)");
+	}
+
+	#[test]
+	fn csv_escape_quotes_fields_with_commas_and_quotes() {
+		assert_eq!(csv_escape("FOO, \"BAR\""), "\"FOO, \"\"BAR\"\"\"");
+	}
+}
@@ -0,0 +1,93 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::STResponse;
+
+/// Every TLE line is 69 columns wide, with column 69 holding a modulo-10 checksum over
+/// columns 1-68 (digits count as their value, '-' counts as 1, everything else as 0).
+const TLE_LINE_LEN: usize = 69;
+
+#[derive(Debug)]
+pub enum TleError {
+	InvalidLength { line: String, actual: usize },
+	ChecksumMismatch { line: String, expected: u32, actual: u32 },
+}
+
+impl fmt::Display for TleError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TleError::InvalidLength { line, actual } =>
+				write!(f, "TLE line has length {} (expected {}): {:?}", actual, TLE_LINE_LEN, line),
+			TleError::ChecksumMismatch { line, expected, actual } =>
+				write!(f, "TLE line checksum mismatch: expected {}, got {}: {:?}", expected, actual, line),
+		}
+	}
+}
+
+impl Error for TleError {}
+
+fn checksum(line: &str) -> u32 {
+	line.chars().take(TLE_LINE_LEN - 1).map(|c| match c {
+		'0'..='9' => c.to_digit(10).unwrap(),
+		'-' => 1,
+		_ => 0,
+	}).sum::<u32>() % 10
+}
+
+/// Validates a single TLE line's length and trailing modulo-10 checksum digit.
+pub fn validate_tle_line(line: &str) -> Result<(), TleError> {
+	let len = line.chars().count();
+	if len != TLE_LINE_LEN {
+		return Err(TleError::InvalidLength { line: line.to_string(), actual: len });
+	}
+	let expected = checksum(line);
+	let actual = line.chars().last().and_then(|c| c.to_digit(10))
+		.ok_or_else(|| TleError::ChecksumMismatch { line: line.to_string(), expected, actual: 10 })?;
+	if actual != expected {
+		return Err(TleError::ChecksumMismatch { line: line.to_string(), expected, actual });
+	}
+	Ok(())
+}
+
+/// Validates both TLE lines of a retrieved element set.
+pub fn validate_response(resp: &STResponse) -> Result<(), TleError> {
+	validate_tle_line(&resp.line_1)?;
+	validate_tle_line(&resp.line_2)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const VALID_LINE_1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+	const VALID_LINE_2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+	#[test]
+	fn validate_tle_line_accepts_correct_checksum() {
+		assert!(validate_tle_line(VALID_LINE_1).is_ok());
+		assert!(validate_tle_line(VALID_LINE_2).is_ok());
+	}
+
+	#[test]
+	fn validate_tle_line_rejects_wrong_checksum_digit() {
+		let mut bad = VALID_LINE_1.to_string();
+		bad.replace_range(68.., "6");
+		match validate_tle_line(&bad) {
+			Err(TleError::ChecksumMismatch { expected, actual, .. }) => {
+				assert_eq!(expected, 7);
+				assert_eq!(actual, 6);
+			}
+			other => panic!("expected ChecksumMismatch, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn validate_tle_line_rejects_wrong_length() {
+		let truncated = &VALID_LINE_1[..68];
+		match validate_tle_line(truncated) {
+			Err(TleError::InvalidLength { actual, .. }) => assert_eq!(actual, 68),
+			other => panic!("expected InvalidLength, got {:?}", other),
+		}
+	}
+}